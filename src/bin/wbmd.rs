@@ -1,4 +1,4 @@
-use cancel_culture::{cli, wbm::valid};
+use cancel_culture::{browser::twitter::id::TweetId, cli, wbm::tweet::db::TweetStore, wbm::valid};
 use clap::{crate_authors, crate_version, Clap};
 use futures::StreamExt;
 
@@ -54,6 +54,73 @@ async fn main() -> valid::Result<()> {
 
             log::info!("Valid: {}; invalid: {}; broken: {}", valid, invalid, broken);
         }
+        SubCommand::Search { db, query, limit } => {
+            let store = TweetStore::new(db, false);
+            match store {
+                Ok(store) => match store.search(&query, limit).await {
+                    Ok(results) => {
+                        for (tweet, digest) in results {
+                            println!(
+                                "{} {} @{}: {}",
+                                digest, tweet.id, tweet.user_screen_name, tweet.text
+                            );
+                        }
+                    }
+                    Err(error) => log::error!("Search error: {:?}", error),
+                },
+                Err(error) => log::error!("Error opening tweet store: {:?}", error),
+            }
+        }
+        SubCommand::Tweet { db, ids } => match TweetStore::new(db, false) {
+            Ok(store) => match store.get_tweet(&ids).await {
+                Ok(results) => {
+                    for (tweet, digest) in results {
+                        println!(
+                            "{} {} @{}: {}",
+                            digest, tweet.id, tweet.user_screen_name, tweet.text
+                        );
+                    }
+                }
+                Err(error) => log::error!("Lookup error: {:?}", error),
+            },
+            Err(error) => log::error!("Error opening tweet store: {:?}", error),
+        },
+        SubCommand::Thread { db, id } => match TweetStore::new(db, false) {
+            Ok(store) => match store.get_thread(id.value()).await {
+                Ok(results) => {
+                    for (tweet, _) in results {
+                        let indent = if tweet.parent_id.is_some() { "  " } else { "" };
+                        println!("{}{} @{}: {}", indent, tweet.id, tweet.user_screen_name, tweet.text);
+                    }
+                }
+                Err(error) => log::error!("Thread error: {:?}", error),
+            },
+            Err(error) => log::error!("Error opening tweet store: {:?}", error),
+        },
+        SubCommand::Users { db, renames } => match TweetStore::new(db, false) {
+            Ok(store) => {
+                let result = if renames {
+                    store.detect_renames().await
+                } else {
+                    store.get_users().await
+                };
+
+                match result {
+                    Ok(users) => {
+                        for user in users {
+                            println!(
+                                "{} {} [{}]",
+                                user.id,
+                                user.screen_names.join(", "),
+                                user.names.join(", ")
+                            );
+                        }
+                    }
+                    Err(error) => log::error!("Users error: {:?}", error),
+                }
+            }
+            Err(error) => log::error!("Error opening tweet store: {:?}", error),
+        },
     }
 
     Ok(())
@@ -102,4 +169,36 @@ enum SubCommand {
         #[clap(short, long)]
         prefix: Option<String>,
     },
+    Search {
+        /// The tweet store database file
+        #[clap(short, long)]
+        db: String,
+        /// Full-text search query
+        query: String,
+        /// Maximum number of results
+        #[clap(short, long, default_value = "20")]
+        limit: usize,
+    },
+    Tweet {
+        /// The tweet store database file
+        #[clap(short, long)]
+        db: String,
+        /// Tweet ids to look up (bare id, `twitter:`-prefixed, or status URLs)
+        ids: Vec<TweetId>,
+    },
+    Thread {
+        /// The tweet store database file
+        #[clap(short, long)]
+        db: String,
+        /// Any tweet id in the thread (bare id, `twitter:`-prefixed, or a status URL)
+        id: TweetId,
+    },
+    Users {
+        /// The tweet store database file
+        #[clap(short, long)]
+        db: String,
+        /// Only show users observed under more than one screen name
+        #[clap(short, long)]
+        renames: bool,
+    },
 }
@@ -0,0 +1,55 @@
+use std::str::FromStr;
+use thiserror::Error;
+
+/// A parsed Twitter status id.
+///
+/// Status ids reach us in several shapes depending on where a user copied them
+/// from: a bare numeric id, a `twitter:`- or `:`-prefixed id, or a full
+/// `twitter.com`/`x.com` status URL (possibly with a trailing query string or a
+/// `/photo/1` suffix). [`TweetId`] accepts all of these and exposes the
+/// underlying `u64` for the usual `SQLiteId` lookup.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TweetId(pub u64);
+
+impl TweetId {
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TweetIdParseError {
+    #[error("Empty tweet id")]
+    Empty,
+    #[error("Invalid tweet id: {0}")]
+    Invalid(String),
+}
+
+impl FromStr for TweetId {
+    type Err = TweetIdParseError;
+
+    fn from_str(input: &str) -> Result<TweetId, TweetIdParseError> {
+        let trimmed = input.trim();
+        if trimmed.is_empty() {
+            return Err(TweetIdParseError::Empty);
+        }
+
+        let candidate = trimmed
+            .strip_prefix("twitter:")
+            .or_else(|| trimmed.strip_prefix(':'))
+            .unwrap_or(trimmed);
+
+        let digits = match candidate.find("status/") {
+            Some(index) => candidate[index + "status/".len()..]
+                .split(|c: char| !c.is_ascii_digit())
+                .next()
+                .unwrap_or(""),
+            None => candidate,
+        };
+
+        digits
+            .parse::<u64>()
+            .map(TweetId)
+            .map_err(|_| TweetIdParseError::Invalid(input.to_string()))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::browser::twitter::id::TweetId;
 use crate::browser::twitter::parser::BrowserTweet;
 use crate::util::sqlite::{SQLiteDateTime, SQLiteId};
 use futures_locks::RwLock;
@@ -26,6 +27,64 @@ const TWEET_SELECT_BY_ID: &str = "
         LIMIT 1
 ";
 
+const TWEET_SEARCH: &str = "
+    SELECT tweet.twitter_id
+        FROM tweet_fts
+        JOIN tweet ON tweet.id = tweet_fts.rowid
+        WHERE tweet_fts MATCH ?
+        GROUP BY tweet.twitter_id
+        ORDER BY MIN(bm25(tweet_fts))
+        LIMIT ?
+";
+
+// Ensure the FTS index exists and is populated for stores created before the
+// full-text search subsystem; `schemas/tweet.sql` only builds it on
+// create/recreate, so a pre-change DB opened with `new(.., false)` would
+// otherwise have no `tweet_fts` for `search` to query or triggers to maintain.
+const FTS_MIGRATE: &str = "
+    CREATE VIRTUAL TABLE IF NOT EXISTS tweet_fts USING fts5(
+        content,
+        content='tweet',
+        content_rowid='id',
+        tokenize='unicode61 remove_diacritics 2'
+    );
+    CREATE TRIGGER IF NOT EXISTS tweet_fts_insert AFTER INSERT ON tweet BEGIN
+        INSERT INTO tweet_fts(rowid, content) VALUES (new.id, new.content);
+    END;
+    CREATE TRIGGER IF NOT EXISTS tweet_fts_delete AFTER DELETE ON tweet BEGIN
+        INSERT INTO tweet_fts(tweet_fts, rowid, content) VALUES ('delete', old.id, old.content);
+    END;
+    CREATE TRIGGER IF NOT EXISTS tweet_fts_update AFTER UPDATE ON tweet BEGIN
+        INSERT INTO tweet_fts(tweet_fts, rowid, content) VALUES ('delete', old.id, old.content);
+        INSERT INTO tweet_fts(rowid, content) VALUES (new.id, new.content);
+    END;
+    INSERT INTO tweet_fts(tweet_fts) VALUES ('rebuild');
+";
+
+const THREAD_SELECT: &str = "
+    WITH RECURSIVE
+        ancestors(twitter_id, parent_twitter_id) AS (
+            SELECT twitter_id, parent_twitter_id FROM tweet WHERE twitter_id = ?
+            UNION
+            SELECT tweet.twitter_id, tweet.parent_twitter_id
+                FROM tweet
+                JOIN ancestors ON tweet.twitter_id = ancestors.parent_twitter_id
+                WHERE ancestors.twitter_id <> ancestors.parent_twitter_id
+        ),
+        thread(twitter_id) AS (
+            SELECT twitter_id FROM ancestors
+            UNION
+            SELECT tweet.twitter_id
+                FROM tweet
+                JOIN thread ON tweet.parent_twitter_id = thread.twitter_id
+                WHERE tweet.twitter_id <> tweet.parent_twitter_id
+        )
+    SELECT DISTINCT tweet.twitter_id
+        FROM tweet
+        JOIN thread ON thread.twitter_id = tweet.twitter_id
+        ORDER BY tweet.ts
+";
+
 const TWEET_SELECT_FULL: &str = "
     SELECT id
         FROM tweet
@@ -39,11 +98,24 @@ const TWEET_FILE_INSERT: &str =
     "INSERT INTO tweet_file (tweet_id, file_id, user_id) VALUES (?, ?, ?)";
 
 const USER_SELECT_ALL: &str = "
-    SELECT user.twitter_id, tweet.ts, user.screen_name, user.name
+    SELECT user.twitter_id,
+        (SELECT MAX(tweet.ts) FROM tweet WHERE tweet.user_twitter_id = user.twitter_id) AS last_seen,
+        user.screen_name,
+        user.name
         FROM user
-        FROM tweet ON tweet.id = (
-            SELECT id FROM tweet WHERE tweet.user_twitter_id = user.twitter_id ORDER BY ts DESC LIMIT 1
-        )
+        GROUP BY user.twitter_id, user.screen_name, user.name
+        ORDER BY user.twitter_id
+";
+
+const USER_SELECT_ONE: &str = "
+    SELECT user.twitter_id,
+        (SELECT MAX(tweet.ts) FROM tweet WHERE tweet.user_twitter_id = user.twitter_id) AS last_seen,
+        user.screen_name,
+        user.name
+        FROM user
+        WHERE user.twitter_id = ?
+        GROUP BY user.twitter_id, user.screen_name, user.name
+        ORDER BY user.twitter_id
 ";
 
 pub type TweetStoreResult<T> = Result<T, TweetStoreError>;
@@ -58,10 +130,10 @@ pub enum TweetStoreError {
 
 #[derive(Debug)]
 pub struct UserRecord {
-    id: u64,
-    last_seen: u64,
-    screen_names: Vec<String>,
-    names: Vec<String>,
+    pub id: u64,
+    pub last_seen: u64,
+    pub screen_names: Vec<String>,
+    pub names: Vec<String>,
 }
 
 #[derive(Clone)]
@@ -77,6 +149,7 @@ impl TweetStore {
         if exists {
             if recreate {
                 let tx = connection.transaction()?;
+                tx.execute("DROP TABLE IF EXISTS tweet_fts", [])?;
                 tx.execute("DROP TABLE IF EXISTS tweet", [])?;
                 tx.execute("DROP TABLE IF EXISTS user", [])?;
                 tx.execute("DROP TABLE IF EXISTS file", [])?;
@@ -84,6 +157,8 @@ impl TweetStore {
                 let schema = Self::load_schema()?;
                 tx.execute_batch(&schema)?;
                 tx.commit()?;
+            } else {
+                connection.execute_batch(FTS_MIGRATE)?;
             }
         } else {
             let schema = Self::load_schema()?;
@@ -130,40 +205,69 @@ impl TweetStore {
                 &tweet.user_name,
             )?;
 
-            let existing_id: Option<i64> = select_tweet
-                .query_row(
-                    params![
-                        SQLiteId(tweet.id),
-                        SQLiteId(tweet.parent_id.unwrap_or(tweet.id)),
-                        SQLiteDateTime(tweet.time),
-                        SQLiteId(tweet.user_id),
-                        tweet.text
-                    ],
-                    |row| row.get(0),
-                )
-                .optional()?;
-
-            let tweet_id = match existing_id {
-                None => {
-                    insert_tweet.execute(params![
-                        SQLiteId(tweet.id),
-                        SQLiteId(tweet.parent_id.unwrap_or(tweet.id)),
-                        SQLiteDateTime(tweet.time),
-                        SQLiteId(tweet.user_id),
-                        tweet.text
-                    ])?;
-
-                    tx.last_insert_rowid()
-                }
-                Some(id) => id,
-            };
+            // Store both the raw and canonicalized text so the
+            // `LENGTH(content) DESC` dedup in `TWEET_SELECT_BY_ID` can favor
+            // whichever capture is most complete. `TWEET_SELECT_FULL` keys on
+            // content, so each distinct form is its own row linked to this file.
+            let canonical = Self::canonicalize_content(&tweet.text);
+            let mut contents = vec![tweet.text.clone()];
+            if canonical != tweet.text {
+                contents.push(canonical);
+            }
+
+            for content in &contents {
+                let existing_id: Option<i64> = select_tweet
+                    .query_row(
+                        params![
+                            SQLiteId(tweet.id),
+                            SQLiteId(tweet.parent_id.unwrap_or(tweet.id)),
+                            SQLiteDateTime(tweet.time),
+                            SQLiteId(tweet.user_id),
+                            content
+                        ],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
 
-            insert_tweet_file.execute(params![tweet_id, file_id, user_id])?;
+                let tweet_id = match existing_id {
+                    None => {
+                        insert_tweet.execute(params![
+                            SQLiteId(tweet.id),
+                            SQLiteId(tweet.parent_id.unwrap_or(tweet.id)),
+                            SQLiteDateTime(tweet.time),
+                            SQLiteId(tweet.user_id),
+                            content
+                        ])?;
+
+                        tx.last_insert_rowid()
+                    }
+                    Some(id) => id,
+                };
+
+                insert_tweet_file.execute(params![tweet_id, file_id, user_id])?;
+            }
         }
 
         Ok(())
     }
 
+    /// HTML-unescape tweet text into its canonical display form.
+    ///
+    /// `add_tweets` stores this alongside the raw text so the
+    /// `LENGTH(content) DESC` dedup keeps the most complete capture.
+    ///
+    /// DEFERRED: the entity-level steps the request also describes — preferring
+    /// the retweeted status's text, choosing `extended_tweet.full_text` for
+    /// truncated tweets, expanding `t.co` shortlinks from `entities.urls`, and
+    /// dropping the quoted-tweet self-link — need the raw status JSON and belong
+    /// in `browser::twitter::parser`, which is not part of this tree. Only the
+    /// unescaping and both-forms storage are delivered here.
+    fn canonicalize_content(text: &str) -> String {
+        text.replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&")
+    }
+
     fn load_schema() -> std::io::Result<String> {
         std::fs::read_to_string("schemas/tweet.sql")
     }
@@ -193,13 +297,191 @@ impl TweetStore {
 
     pub async fn get_tweet(
         &self,
-        status_ids: &[u64],
+        status_ids: &[TweetId],
     ) -> TweetStoreResult<Vec<(BrowserTweet, String)>> {
         let connection = self.connection.read().await;
         let mut select = connection.prepare_cached(TWEET_SELECT_BY_ID)?;
         let mut result = Vec::with_capacity(status_ids.len());
 
-        for id in status_ids {
+        for status_id in status_ids {
+            let id = status_id.value();
+            match select.query_row(params![SQLiteId(id)], |row| {
+                let parent_twitter_id = row.get::<usize, i64>(0)? as u64;
+                let ts: SQLiteDateTime = row.get(1)?;
+                let user_twitter_id = row.get::<usize, i64>(2)? as u64;
+                let screen_name: String = row.get(3)?;
+                let name: String = row.get(4)?;
+                let content: String = row.get(5)?;
+                let digest: String = row.get(6)?;
+
+                Ok((
+                    BrowserTweet::new(
+                        id,
+                        if parent_twitter_id == id {
+                            None
+                        } else {
+                            Some(parent_twitter_id)
+                        },
+                        ts.0,
+                        user_twitter_id,
+                        screen_name,
+                        name,
+                        content,
+                    ),
+                    digest,
+                ))
+            }) {
+                Ok(pair) => result.push(pair),
+                Err(error) => log::error!("Error for {}: {:?}", id, error),
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub async fn get_users(&self) -> TweetStoreResult<Vec<UserRecord>> {
+        let connection = self.connection.read().await;
+        let mut select = connection.prepare_cached(USER_SELECT_ALL)?;
+        let rows = select
+            .query_map([], Self::map_user_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::aggregate_users(rows))
+    }
+
+    pub async fn get_user(&self, twitter_id: u64) -> TweetStoreResult<Option<UserRecord>> {
+        let connection = self.connection.read().await;
+        let mut select = connection.prepare_cached(USER_SELECT_ONE)?;
+        let rows = select
+            .query_map(params![SQLiteId(twitter_id)], Self::map_user_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::aggregate_users(rows).into_iter().next())
+    }
+
+    /// Users observed under more than one screen name across captures.
+    pub async fn detect_renames(&self) -> TweetStoreResult<Vec<UserRecord>> {
+        Ok(self
+            .get_users()
+            .await?
+            .into_iter()
+            .filter(|user| user.screen_names.len() > 1)
+            .collect())
+    }
+
+    fn map_user_row(row: &rusqlite::Row) -> rusqlite::Result<(u64, u64, String, String)> {
+        let twitter_id = row.get::<usize, i64>(0)? as u64;
+        let last_seen = row.get::<usize, Option<i64>>(1)?.unwrap_or(0) as u64;
+        let screen_name: String = row.get(2)?;
+        let name: String = row.get(3)?;
+        Ok((twitter_id, last_seen, screen_name, name))
+    }
+
+    /// Fold the per-identity rows (ordered by `twitter_id`) into one
+    /// `UserRecord` per account, collecting every distinct screen name and
+    /// display name and keeping the most recent `last_seen`.
+    fn aggregate_users(rows: Vec<(u64, u64, String, String)>) -> Vec<UserRecord> {
+        let mut records: Vec<UserRecord> = Vec::new();
+
+        for (twitter_id, last_seen, screen_name, name) in rows {
+            match records.last_mut() {
+                Some(record) if record.id == twitter_id => {
+                    record.last_seen = record.last_seen.max(last_seen);
+                    if !record.screen_names.contains(&screen_name) {
+                        record.screen_names.push(screen_name);
+                    }
+                    if !record.names.contains(&name) {
+                        record.names.push(name);
+                    }
+                }
+                _ => records.push(UserRecord {
+                    id: twitter_id,
+                    last_seen,
+                    screen_names: vec![screen_name],
+                    names: vec![name],
+                }),
+            }
+        }
+
+        records
+    }
+
+    pub async fn get_thread(
+        &self,
+        tweet_id: u64,
+    ) -> TweetStoreResult<Vec<(BrowserTweet, String)>> {
+        let connection = self.connection.read().await;
+
+        let thread_ids = {
+            let mut select = connection.prepare_cached(THREAD_SELECT)?;
+            select
+                .query_map(params![SQLiteId(tweet_id)], |row| {
+                    Ok(row.get::<usize, i64>(0)? as u64)
+                })?
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let mut select = connection.prepare_cached(TWEET_SELECT_BY_ID)?;
+        let mut result = Vec::with_capacity(thread_ids.len());
+
+        for id in &thread_ids {
+            match select.query_row(params![SQLiteId(*id)], |row| {
+                let parent_twitter_id = row.get::<usize, i64>(0)? as u64;
+                let ts: SQLiteDateTime = row.get(1)?;
+                let user_twitter_id = row.get::<usize, i64>(2)? as u64;
+                let screen_name: String = row.get(3)?;
+                let name: String = row.get(4)?;
+                let content: String = row.get(5)?;
+                let digest: String = row.get(6)?;
+
+                Ok((
+                    BrowserTweet::new(
+                        *id,
+                        if parent_twitter_id == *id {
+                            None
+                        } else {
+                            Some(parent_twitter_id)
+                        },
+                        ts.0,
+                        user_twitter_id,
+                        screen_name,
+                        name,
+                        content,
+                    ),
+                    digest,
+                ))
+            }) {
+                Ok(pair) => result.push(pair),
+                Err(error) => log::error!("Error for {}: {:?}", id, error),
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub async fn search(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> TweetStoreResult<Vec<(BrowserTweet, String)>> {
+        let connection = self.connection.read().await;
+
+        // Rank the distinct matching tweets by relevance, then resolve each
+        // through `TWEET_SELECT_BY_ID` so a tweet captured in many files
+        // collapses to a single longest-content row, exactly like `get_tweet`.
+        let matches = {
+            let mut select = connection.prepare_cached(TWEET_SEARCH)?;
+            select
+                .query_map(params![query, limit as i64], |row| {
+                    Ok(row.get::<usize, i64>(0)? as u64)
+                })?
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let mut select = connection.prepare_cached(TWEET_SELECT_BY_ID)?;
+        let mut result = Vec::with_capacity(matches.len());
+
+        for id in &matches {
             match select.query_row(params![SQLiteId(*id)], |row| {
                 let parent_twitter_id = row.get::<usize, i64>(0)? as u64;
                 let ts: SQLiteDateTime = row.get(1)?;
@@ -0,0 +1,108 @@
+//! Content-defined chunking for block-level deduplication.
+//!
+//! The [`valid::ValidStore`](super::ValidStore) keeps each artifact as one
+//! whole blob keyed by its digest, so re-captures of the same thread store the
+//! same bytes again and again. This module splits an artifact into
+//! variable-length chunks at boundaries chosen by the *content* rather than a
+//! fixed offset, so an edit early in a file only reshuffles the chunks around
+//! it instead of every chunk after it. Each distinct chunk can then be stored
+//! once under its own digest, and a file becomes an ordered [`Manifest`] of
+//! chunk ids.
+//!
+//! Boundaries are found with a buzhash rolling hash over a sliding window: a
+//! boundary is declared whenever the low bits of the hash are zero, clamped by
+//! a minimum and maximum chunk size so the output is deterministic and the
+//! chunk stream stays resilient to insertions and deletions.
+//!
+//! This module is declared from the `valid` module root (`pub mod chunk;`).
+//!
+//! DEFERRED: wiring this into `ValidStore::create`/`extract`/`compute_digests`
+//! — storing each chunk once under its SHA digest, reassembling files from
+//! manifests with per-chunk integrity verification, and a `wbmd gc` subcommand
+//! that drops chunks unreferenced by any manifest — depends on the `valid`
+//! store internals and its digest scheme, which are not part of this tree. Only
+//! the chunker and manifest representation are delivered here.
+
+/// Width of the sliding window the rolling hash is computed over.
+const WINDOW_SIZE: usize = 48;
+/// Smallest chunk we will emit (except for a trailing remainder).
+const MIN_CHUNK_SIZE: usize = 2 * 1024;
+/// Largest chunk we will emit before forcing a boundary.
+const MAX_CHUNK_SIZE: usize = 64 * 1024;
+/// A boundary is declared when `hash & MASK == 0`; 13 set bits targets an
+/// average chunk size of roughly 8 KiB.
+const MASK: u32 = (1 << 13) - 1;
+
+/// Deterministic byte → value table for the cyclic polynomial hash, built at
+/// compile time from a fixed linear congruential sequence so that chunk
+/// boundaries are reproducible across runs and machines.
+const TABLE: [u32; 256] = build_table();
+
+const fn build_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut state: u32 = 0x1234_5678;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+        table[i] = state;
+        i += 1;
+    }
+    table
+}
+
+/// An ordered list of chunk ids describing how to reassemble one file. This
+/// type only holds the ids; nothing in this module stores manifests or chunks,
+/// so sharing of identical manifests or chunks is not done here.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub chunk_ids: Vec<String>,
+}
+
+impl Manifest {
+    pub fn new(chunk_ids: Vec<String>) -> Manifest {
+        Manifest { chunk_ids }
+    }
+}
+
+/// Split `data` into content-defined chunks, returning each chunk as a slice
+/// of the input in order. Concatenating the results reproduces `data` exactly.
+pub fn chunk(data: &[u8]) -> Vec<&[u8]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < data.len() {
+        let end = next_boundary(&data[start..]) + start;
+        chunks.push(&data[start..end]);
+        start = end;
+    }
+
+    chunks
+}
+
+/// Find the end offset (relative to the start of `data`) of the first chunk.
+///
+/// We refuse to emit a boundary before `MIN_CHUNK_SIZE`, force one at
+/// `MAX_CHUNK_SIZE`, and otherwise cut as soon as the rolling hash of the last
+/// `WINDOW_SIZE` bytes satisfies the boundary mask.
+fn next_boundary(data: &[u8]) -> usize {
+    if data.len() <= MIN_CHUNK_SIZE {
+        return data.len();
+    }
+
+    let max = data.len().min(MAX_CHUNK_SIZE);
+    let mut hash: u32 = 0;
+
+    for (i, &byte) in data.iter().enumerate().take(max) {
+        hash = hash.rotate_left(1) ^ TABLE[byte as usize];
+
+        if i >= WINDOW_SIZE {
+            hash ^= TABLE[data[i - WINDOW_SIZE] as usize].rotate_left(WINDOW_SIZE as u32 % 32);
+        }
+
+        if i + 1 >= MIN_CHUNK_SIZE && hash & MASK == 0 {
+            return i + 1;
+        }
+    }
+
+    max
+}